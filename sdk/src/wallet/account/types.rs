@@ -0,0 +1,141 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::AddAssign;
+
+use primitive_types::U256;
+
+use crate::types::block::{
+    address::Bech32Address,
+    output::{feature::MetadataFeature, AliasId, FoundryId, NftId, OutputId, TokenId},
+};
+
+/// An address and its unspent output ids.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddressWithUnspentOutputs {
+    /// The address.
+    pub address: Bech32Address,
+    /// The unspent output ids owned by this address.
+    pub output_ids: Vec<OutputId>,
+}
+
+/// The balance of an account.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Balance {
+    /// Total and available amount of the base coin.
+    pub base_coin: BaseCoinBalance,
+    /// Current required storage deposit amount.
+    pub required_storage_deposit: RequiredStorageDeposit,
+    /// Native tokens.
+    pub native_tokens: Vec<NativeTokensBalance>,
+    /// Nfts.
+    pub nfts: Vec<NftId>,
+    /// Aliases.
+    pub aliases: Vec<AliasId>,
+    /// Foundries.
+    pub foundries: Vec<FoundryId>,
+    /// Outputs with multiple unlock conditions and if they can currently be spent or not. If not, the
+    /// wallet can't know if the balance will ever be available, since it might need a transaction from
+    /// another party to be unlocked again.
+    pub potentially_locked_outputs: std::collections::HashMap<OutputId, bool>,
+    /// Value that's still ours but will flip to the return address once each output's expiration slot
+    /// passes.
+    pub expiring_soon: ExpiringBalance,
+    /// Value we'll only gain control over once the counterparty's expiration window lapses.
+    pub incoming_on_expiration: ExpiringBalance,
+}
+
+impl AddAssign for Balance {
+    fn add_assign(&mut self, other: Self) {
+        self.base_coin += other.base_coin;
+        self.required_storage_deposit += other.required_storage_deposit;
+        self.native_tokens.extend(other.native_tokens);
+        self.nfts.extend(other.nfts);
+        self.aliases.extend(other.aliases);
+        self.foundries.extend(other.foundries);
+        self.potentially_locked_outputs.extend(other.potentially_locked_outputs);
+        self.expiring_soon += other.expiring_soon;
+        self.incoming_on_expiration += other.incoming_on_expiration;
+    }
+}
+
+/// Total and available amount of the base coin.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaseCoinBalance {
+    /// Total amount.
+    pub total: u64,
+    /// Amount currently available to spend.
+    pub available: u64,
+    /// Amount reserved for voting power, if the `participation` feature is enabled.
+    #[cfg(feature = "participation")]
+    pub voting_power: u64,
+}
+
+impl AddAssign for BaseCoinBalance {
+    fn add_assign(&mut self, other: Self) {
+        self.total += other.total;
+        self.available += other.available;
+        #[cfg(feature = "participation")]
+        {
+            self.voting_power += other.voting_power;
+        }
+    }
+}
+
+/// Current required storage deposit, per output kind.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RequiredStorageDeposit {
+    /// Required storage deposit for alias outputs.
+    pub alias: u64,
+    /// Required storage deposit for basic outputs.
+    pub basic: u64,
+    /// Required storage deposit for foundry outputs.
+    pub foundry: u64,
+    /// Required storage deposit for nft outputs.
+    pub nft: u64,
+}
+
+impl AddAssign for RequiredStorageDeposit {
+    fn add_assign(&mut self, other: Self) {
+        self.alias += other.alias;
+        self.basic += other.basic;
+        self.foundry += other.foundry;
+        self.nft += other.nft;
+    }
+}
+
+/// The balance of a native token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NativeTokensBalance {
+    /// The token id.
+    pub token_id: TokenId,
+    /// The total amount.
+    pub total: U256,
+    /// The amount currently available to spend.
+    pub available: U256,
+    /// The metadata of the foundry that minted this token, if known.
+    pub metadata: Option<MetadataFeature>,
+}
+
+/// Value that's about to change ownership via an expiration unlock condition, either away from us
+/// ([`Balance::expiring_soon`]) or towards us ([`Balance::incoming_on_expiration`]).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpiringBalance {
+    /// The base coin amount.
+    pub amount: u64,
+    /// The native tokens.
+    pub native_tokens: Vec<NativeTokensBalance>,
+}
+
+impl AddAssign for ExpiringBalance {
+    fn add_assign(&mut self, other: Self) {
+        self.amount += other.amount;
+        self.native_tokens.extend(other.native_tokens);
+    }
+}
@@ -43,20 +43,19 @@ pub(crate) fn can_output_be_unlocked_forever_from_now_on(
             return false;
         }
 
-        // TODO HELP
-        // // If there is an expiration unlock condition, we can only unlock it forever from now on, if it's expired and
-        // // the return address belongs to the account
-        // if let Some(expiration) = unlock_conditions.expiration() {
-        //     if let Some(return_address) =
-        //         expiration.return_address_expired(slot_index, min_committable_age, max_committable_age)
-        //     {
-        //         if wallet_address != return_address {
-        //             return false;
-        //         };
-        //     } else {
-        //         return false;
-        //     }
-        // }
+        // If there is an expiration unlock condition, we can only unlock it forever from now on, if it's expired and
+        // the return address belongs to the account
+        if let Some(expiration) = unlock_conditions.expiration() {
+            if let Some(return_address) =
+                expiration.return_address_expired(slot_index, min_committable_age, max_committable_age)
+            {
+                if wallet_address != return_address {
+                    return false;
+                };
+            } else {
+                return false;
+            }
+        }
 
         true
     } else {